@@ -1,11 +1,15 @@
 #![doc = include_str!("../README.md")]
 #![forbid(unsafe_code)]
 
-#[cfg(not(feature = "ahash"))]
 use std::collections::HashMap;
 
+/// The [`BuildHasher`](core::hash::BuildHasher) used by the ngram containers
+/// unless overridden per instance via the `S` type parameter.
+#[cfg(not(feature = "ahash"))]
+type DefaultHashBuilder = std::collections::hash_map::RandomState;
+
 #[cfg(feature = "ahash")]
-use ahash::HashMap;
+type DefaultHashBuilder = ahash::RandomState;
 
 /// A trait representing a container for ngram counts.
 pub trait Ngrams<G = char>: Default
@@ -18,6 +22,15 @@ where
     #[doc(hidden)]
     fn _chrf_impl(beta: f64, tl: &Self, refs: &Self) -> (f64, usize);
 
+    #[doc(hidden)]
+    fn _stats_impl(tl: &Self, refs: &Self, stats: &mut Vec<(u64, u64, u64)>);
+
+    #[doc(hidden)]
+    fn _chrf_multi_impl(beta: f64, tl: &Self, refs: &[&Self]) -> (f64, usize);
+
+    #[doc(hidden)]
+    fn _chrf_eff_impl(beta: f64, tl: &Self, refs: &Self) -> (f64, usize);
+
     /// Adds all of the items from `iter`.
     fn feed_from(&mut self, iter: impl IntoIterator<Item = G>);
 
@@ -25,10 +38,16 @@ where
     fn clear(&mut self);
 }
 
-#[derive(Default, Debug)]
-struct N0<G>(core::marker::PhantomData<G>);
+#[derive(Debug)]
+struct N0<G, S = DefaultHashBuilder>(core::marker::PhantomData<(G, S)>);
+
+impl<G, S> Default for N0<G, S> {
+    fn default() -> Self {
+        N0(core::marker::PhantomData)
+    }
+}
 
-impl<G> Ngrams<G> for N0<G>
+impl<G, S> Ngrams<G> for N0<G, S>
 where
     G: Copy + Default,
 {
@@ -36,6 +55,13 @@ where
     fn _chrf_impl(_beta: f64, _tl: &Self, _refs: &Self) -> (f64, usize) {
         (0.0, 0)
     }
+    fn _stats_impl(_tl: &Self, _refs: &Self, _stats: &mut Vec<(u64, u64, u64)>) {}
+    fn _chrf_multi_impl(_beta: f64, _tl: &Self, _refs: &[&Self]) -> (f64, usize) {
+        (0.0, 0)
+    }
+    fn _chrf_eff_impl(_beta: f64, _tl: &Self, _refs: &Self) -> (f64, usize) {
+        (0.0, 0)
+    }
     fn feed_from(&mut self, _iter: impl IntoIterator<Item = G>) {}
     fn clear(&mut self) {}
 }
@@ -44,16 +70,19 @@ macro_rules! impl_ngrams {
     ($(($name:ident = $width:expr, $next:ident))*) => {
         $(
             #[derive(Default, Debug)]
-            pub struct $name<G = char> {
-                ngrams: HashMap<[G; $width], u32>,
-                next: $next<G>,
+            pub struct $name<G = char, S = DefaultHashBuilder> {
+                ngrams: HashMap<[G; $width], u32, S>,
+                next: $next<G, S>,
             }
 
             const _: () = {
                 assert!($width != 0);
             };
 
-            impl From<&str> for $name<char> {
+            impl<S> From<&str> for $name<char, S>
+            where
+                S: core::hash::BuildHasher + Default,
+            {
                 fn from(text: &str) -> Self {
                     let mut out = Self::default();
                     out.feed(text);
@@ -61,14 +90,21 @@ macro_rules! impl_ngrams {
                 }
             }
 
-            impl $name<char> {
+            impl<S> $name<char, S>
+            where
+                S: core::hash::BuildHasher + Default,
+            {
                 /// Adds all of the ngrams from `text` except spaces.
                 fn feed(&mut self, text: &str) {
                     self.feed_from(text.chars().filter(|&ch| ch != ' '))
                 }
             }
 
-            impl<G> Ngrams<G> for $name<G> where G: Copy + Default + PartialEq + Eq + core::hash::Hash {
+            impl<G, S> Ngrams<G> for $name<G, S>
+            where
+                G: Copy + Default + PartialEq + Eq + core::hash::Hash,
+                S: core::hash::BuildHasher + Default,
+            {
                 #[inline(always)]
                 fn _feed_impl<const N: usize>(&mut self, count: usize, buffer: [G; N]) {
                     assert!(N >= $width);
@@ -120,6 +156,124 @@ macro_rules! impl_ngrams {
                     (score + next_score, next_count + 1)
                 }
 
+                #[inline(always)]
+                fn _stats_impl(tl: &Self, refs: &Self, stats: &mut Vec<(u64, u64, u64)>) {
+                    let mut total_tl = 0;
+                    for &count_tl in tl.ngrams.values() {
+                        total_tl += count_tl as u64;
+                    }
+
+                    let mut matching = 0;
+                    let mut total_ref = 0;
+                    for (ngram, &count_ref) in &refs.ngrams {
+                        total_ref += count_ref as u64;
+                        if let Some(&count_tl) = tl.ngrams.get(ngram) {
+                            matching += core::cmp::min(count_ref, count_tl) as u64;
+                        }
+                    }
+
+                    stats.push((matching, total_tl, total_ref));
+                    Ngrams::_stats_impl(&tl.next, &refs.next, stats);
+                }
+
+                #[inline(always)]
+                fn _chrf_multi_impl(beta: f64, tl: &Self, refs: &[&Self]) -> (f64, usize) {
+                    let mut total_tl = 0;
+                    for &count_tl in tl.ngrams.values() {
+                        total_tl += count_tl;
+                    }
+
+                    let beta2 = beta.powi(2);
+                    let mut best = 0.0;
+                    for one_ref in refs {
+                        let mut matching = 0;
+                        let mut total_ref = 0;
+                        for (ngram, &count_ref) in &one_ref.ngrams {
+                            total_ref += count_ref;
+                            if let Some(&count_tl) = tl.ngrams.get(ngram) {
+                                matching += core::cmp::min(count_ref, count_tl);
+                            }
+                        }
+
+                        let chr_tl = if total_tl > 0 {
+                            matching as f64 / total_tl as f64
+                        } else {
+                            1e-16
+                        };
+
+                        let chr_ref = if total_ref > 0 {
+                            matching as f64 / total_ref as f64
+                        } else {
+                            1e-16
+                        };
+
+                        let numerator = (1.0 + beta2) * (chr_tl * chr_ref);
+                        let mut denominator = beta2 * chr_tl + chr_ref;
+                        if denominator < 1e-16 {
+                            denominator = 1e-16;
+                        }
+
+                        let score = numerator / denominator;
+                        if score > best {
+                            best = score;
+                        }
+                    }
+
+                    let next_refs: Vec<&$next<G, S>> = refs.iter().map(|r| &r.next).collect();
+                    let (next_score, next_count) =
+                        Ngrams::_chrf_multi_impl(beta, &tl.next, &next_refs);
+                    (best + next_score, next_count + 1)
+                }
+
+                #[inline(always)]
+                fn _chrf_eff_impl(beta: f64, tl: &Self, refs: &Self) -> (f64, usize) {
+                    let mut total_tl = 0;
+                    for &count_tl in tl.ngrams.values() {
+                        total_tl += count_tl;
+                    }
+
+                    let mut matching = 0;
+                    let mut total_ref = 0;
+                    for (ngram, &count_ref) in &refs.ngrams {
+                        total_ref += count_ref;
+                        if let Some(&count_tl) = tl.ngrams.get(ngram) {
+                            matching += core::cmp::min(count_ref, count_tl);
+                        }
+                    }
+
+                    let (next_score, next_count) =
+                        Ngrams::_chrf_eff_impl(beta, &tl.next, &refs.next);
+
+                    // Effective order: if neither side has any n-grams of this
+                    // order (e.g. a segment too short to contain them) skip it
+                    // entirely instead of letting an empty order drag the mean down.
+                    if total_tl == 0 && total_ref == 0 {
+                        return (next_score, next_count);
+                    }
+
+                    let chr_tl = if total_tl > 0 {
+                        matching as f64 / total_tl as f64
+                    } else {
+                        1e-16
+                    };
+
+                    let chr_ref = if total_ref > 0 {
+                        matching as f64 / total_ref as f64
+                    } else {
+                        1e-16
+                    };
+
+                    let beta2 = beta.powi(2);
+                    let numerator = (1.0 + beta2) * (chr_tl * chr_ref);
+                    let mut denominator = beta2 * chr_tl + chr_ref;
+                    if denominator < 1e-16 {
+                        denominator = 1e-16;
+                    }
+
+                    let score = numerator / denominator;
+                    (score + next_score, next_count + 1)
+                }
+
                 fn clear(&mut self) {
                     self.ngrams.clear();
                     self.next.clear();
@@ -174,6 +328,150 @@ pub fn chrf3(translation: &N6, reference: &N6) -> f64 {
     chrf(3.0, translation, reference) * 100.0
 }
 
+/// Calculates a chrF++ score.
+///
+/// This augments the character n-grams scored by [chrf] with word unigrams and
+/// word bigrams (split on whitespace), and averages the per-order F-scores of
+/// both the character and word orders together under a single arithmetic mean.
+///
+/// NOTE: Like [chrf] the score returned by this function is *not* multiplied by 100.
+pub fn chrf_plus(beta: f64, translation: &str, reference: &str) -> f64 {
+    let tl_chars = N6::<char>::from(translation);
+    let ref_chars = N6::<char>::from(reference);
+    let (char_sum, char_count) = Ngrams::_chrf_impl(beta, &tl_chars, &ref_chars);
+
+    let mut tl_words = N2::<&str>::default();
+    tl_words.feed_from(translation.split_whitespace());
+    let mut ref_words = N2::<&str>::default();
+    ref_words.feed_from(reference.split_whitespace());
+    let (word_sum, word_count) = Ngrams::_chrf_impl(beta, &tl_words, &ref_words);
+
+    (char_sum + word_sum) / (char_count + word_count) as f64
+}
+
+/// Calculates a chrF score with effective-order smoothing.
+///
+/// Any n-gram order that has zero n-grams on *both* the translation and the
+/// reference side is skipped entirely: it contributes neither to the score nor
+/// to the order count. This avoids penalizing short segments that cannot
+/// contain high-order n-grams, matching sacreBLEU's effective-order behavior.
+/// Callers who want the unsmoothed averaging should keep using [chrf]/[chrf3].
+///
+/// NOTE: Like [chrf] the score returned by this function is *not* multiplied by 100.
+pub fn chrf_eff<T>(beta: f64, translation: &T, reference: &T) -> f64
+where
+    T: Ngrams,
+{
+    let (sum, count) = T::_chrf_eff_impl(beta, translation, reference);
+    if count == 0 {
+        return 0.0;
+    }
+    sum / count as f64
+}
+
+/// Calculates a chrF score against multiple references.
+///
+/// For each n-gram order the F-score is computed against every reference and
+/// the best (maximum) is kept before averaging across orders. Note that the
+/// maximum is taken independently per order, so the averaged result can combine
+/// best-matching orders from different references rather than picking a single
+/// reference. With a single reference this is equivalent to [chrf].
+///
+/// NOTE: Like [chrf] the score returned by this function is *not* multiplied by 100.
+pub fn chrf_multi<T>(beta: f64, translation: &T, references: &[&T]) -> f64
+where
+    T: Ngrams,
+{
+    if references.len() == 1 {
+        return chrf(beta, translation, references[0]);
+    }
+
+    let (sum, count) = T::_chrf_multi_impl(beta, translation, references);
+    sum / count as f64
+}
+
+/// A statistics accumulator for corpus-level chrF.
+///
+/// Corpus chrF is *not* the mean of per-sentence scores; instead the matching,
+/// translation and reference n-gram counts are summed across every sentence
+/// pair and the F-score is computed from the aggregated (micro-averaged) counts.
+/// This matches sacreBLEU's corpus chrF and lets a whole test set be scored
+/// without re-feeding each pair.
+pub struct ChrfStats<T = N6> {
+    /// `(matching, total_tl, total_ref)` summed per n-gram order.
+    stats: Vec<(u64, u64, u64)>,
+    _marker: core::marker::PhantomData<T>,
+}
+
+impl<T> Default for ChrfStats<T> {
+    fn default() -> Self {
+        ChrfStats {
+            stats: Vec::new(),
+            _marker: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<T> ChrfStats<T>
+where
+    T: Ngrams,
+{
+    /// Creates an empty accumulator.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Folds a single translation/reference pair into the accumulated counts.
+    pub fn add(&mut self, tl: &T, refs: &T) {
+        let mut pair = Vec::new();
+        T::_stats_impl(tl, refs, &mut pair);
+        if self.stats.is_empty() {
+            self.stats = pair;
+        } else {
+            for (acc, cur) in self.stats.iter_mut().zip(pair) {
+                acc.0 += cur.0;
+                acc.1 += cur.1;
+                acc.2 += cur.2;
+            }
+        }
+    }
+
+    /// Computes the corpus-level chrF score from the aggregated counts.
+    ///
+    /// NOTE: Like [chrf] the score returned by this function is *not* multiplied by 100.
+    pub fn score(&self, beta: f64) -> f64 {
+        if self.stats.is_empty() {
+            return 0.0;
+        }
+
+        let beta2 = beta.powi(2);
+        let mut sum = 0.0;
+        for &(matching, total_tl, total_ref) in &self.stats {
+            let chr_tl = if total_tl > 0 {
+                matching as f64 / total_tl as f64
+            } else {
+                1e-16
+            };
+
+            let chr_ref = if total_ref > 0 {
+                matching as f64 / total_ref as f64
+            } else {
+                1e-16
+            };
+
+            let numerator = (1.0 + beta2) * (chr_tl * chr_ref);
+            let mut denominator = beta2 * chr_tl + chr_ref;
+            if denominator < 1e-16 {
+                denominator = 1e-16;
+            }
+
+            sum += numerator / denominator;
+        }
+
+        sum / self.stats.len() as f64
+    }
+}
+
 #[test]
 fn test_chrf3() {
     {
@@ -196,3 +494,102 @@ fn test_chrf3() {
         );
     }
 }
+
+#[test]
+fn test_chrf_plus() {
+    {
+        let text = "the cat sat";
+        let score = chrf_plus(2.0, text, text);
+        assert!(
+            (score - 1.0).abs() < 1e-12,
+            "unexpected score: {score} (identical)"
+        );
+    }
+
+    {
+        let tl = "the cat sat on the mat";
+        let refs = "the cat sat on a mat";
+        let score = chrf_plus(2.0, tl, refs);
+        assert!(
+            (score - 0.7197682109).abs() < 1e-10,
+            "unexpected score: {score} (known value)"
+        );
+    }
+}
+
+#[test]
+fn test_chrf_stats() {
+    // A single pair must reproduce the per-sentence `chrf`.
+    {
+        let tl: N6 = "aoeu33".into();
+        let refs: N6 = "axeu33".into();
+        let mut stats = ChrfStats::default();
+        stats.add(&tl, &refs);
+        let expected = chrf(3.0, &tl, &refs);
+        let score = stats.score(3.0);
+        assert!(
+            (score - expected).abs() < 1e-12,
+            "unexpected score: {score} (single pair, expected {expected})"
+        );
+    }
+
+    // Across several pairs the micro-averaged corpus score must differ from the
+    // mean of the per-sentence scores.
+    {
+        let pairs = [
+            ("the cat sat on the mat", "the cat sat on a mat"),
+            ("hello world", "goodbye cruel world"),
+        ];
+
+        let mut stats = ChrfStats::default();
+        let mut macro_sum = 0.0;
+        for &(tl, refs) in &pairs {
+            let tl: N6 = tl.into();
+            let refs: N6 = refs.into();
+            stats.add(&tl, &refs);
+            macro_sum += chrf(3.0, &tl, &refs);
+        }
+
+        let corpus = stats.score(3.0);
+        let macro_mean = macro_sum / pairs.len() as f64;
+        assert!(
+            (corpus - macro_mean).abs() > 1e-6,
+            "corpus score {corpus} unexpectedly equals macro mean {macro_mean}"
+        );
+    }
+}
+
+#[test]
+fn test_chrf_eff() {
+    // A short identical pair has no n-grams of order 3..=6 on either side.
+    let tl: N6 = "ab".into();
+    let refs: N6 = "ab".into();
+
+    let eff = chrf_eff(3.0, &tl, &refs);
+    assert!(
+        (eff - 1.0).abs() < 1e-12,
+        "unexpected effective-order score: {eff}"
+    );
+
+    // Without effective-order smoothing the empty high orders drag the mean down.
+    let plain = chrf(3.0, &tl, &refs);
+    assert!(plain < 1.0, "unexpected unsmoothed score: {plain}");
+}
+
+#[test]
+fn test_custom_hasher() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::BuildHasherDefault;
+
+    type Fixed = BuildHasherDefault<DefaultHasher>;
+
+    let tl: N6<char, Fixed> = "aoeu33".into();
+    let refs: N6<char, Fixed> = "axeu33".into();
+
+    // Scoring through a non-default `BuildHasher` must agree with the default one.
+    let score = chrf(3.0, &tl, &refs) * 100.0;
+    assert!(
+        (score - 37.7778).abs() < 0.0001,
+        "unexpected score: {score}"
+    );
+}